@@ -2,15 +2,47 @@ use sfml::graphics::{
     Color, RenderTarget, RenderWindow, Transformable, CircleShape, Shape
 };
 use sfml::system::{Vector2i, Vector2f};
-use sfml::window::{ContextSettings, Event, Key, Style, VideoMode};
+use sfml::window::{mouse, ContextSettings, Event, Key, Style, VideoMode};
 use rand::Rng;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use std::path::Path;
+
+mod pattern;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
    #[arg(short, long)]
    fullscreen: bool,
+
+   #[arg(long, value_enum, default_value_t = Mode::Conway)]
+   mode: Mode,
+
+   #[arg(long)]
+   load: Option<String>,
+
+   #[arg(long)]
+   torus: bool,
+
+   #[arg(long)]
+   terminal: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Mode {
+    /// Conway's Game of Life (the default).
+    Conway,
+    /// Grass/rabbit/fox ecosystem with predator-prey dynamics.
+    Predprey,
+}
+
+/// The contents of a single cell when running in predator-prey mode.
+#[derive(Clone, Copy, PartialEq)]
+enum Square {
+    Empty,
+    Grass,
+    Rabbit,
+    Fox,
 }
 
 struct Board {
@@ -18,6 +50,10 @@ struct Board {
     cols: usize,
     data: Vec<bool>,
     colours: Vec<u8>,
+    squares: Vec<Square>,
+    energy: Vec<u8>,
+    age: Vec<u16>,
+    wrap: bool,
 }
 
 impl Board {
@@ -28,7 +64,43 @@ impl Board {
         for i in &mut colours {
             *i = rng.gen_range(128..=255);
         }
-        Board{ rows: row, cols: col, data: vec, colours: colours }
+        let squares = vec![Square::Empty; col * row];
+        let energy = vec![0; col * row];
+        let age = vec![0; col * row];
+        Board{ rows: row, cols: col, data: vec, colours: colours,
+               squares: squares, energy: energy, age: age, wrap: false }
+    }
+
+    fn get_age(&self, col: usize, row: usize) -> u16 {
+        if row >= self.rows || col >= self.cols {
+            panic!("Out of bounds");
+        }
+        let offset = row * self.cols + col;
+        return self.age[offset];
+    }
+
+    fn set_age(&mut self, col: usize, row: usize, value: u16) {
+        if row >= self.rows || col >= self.cols {
+            panic!("Out of bounds");
+        }
+        let offset = row * self.cols + col;
+        self.age[offset] = value;
+    }
+
+    fn get_square(&self, col: usize, row: usize) -> Square {
+        if row >= self.rows || col >= self.cols {
+            panic!("Out of bounds");
+        }
+        let offset = row * self.cols + col;
+        return self.squares[offset];
+    }
+
+    fn set_square(&mut self, col: usize, row: usize, value: Square) {
+        if row >= self.rows || col >= self.cols {
+            panic!("Out of bounds");
+        }
+        let offset = row * self.cols + col;
+        self.squares[offset] = value;
     }
 
     fn get(&self, col: usize, row: usize) -> bool {
@@ -75,6 +147,22 @@ impl Board {
             self.data[rng.gen_range(0..self.cols * self.rows)] = true;
         }
     }
+
+    fn randomise_predprey(&mut self) {
+        let mut rng = rand::thread_rng();
+        for i in 0..self.cols * self.rows {
+            self.squares[i] = match rng.gen_range(0..100) {
+                0..=39 => Square::Grass,
+                40..=49 => Square::Rabbit,
+                50..=52 => Square::Fox,
+                _ => Square::Empty,
+            };
+            self.energy[i] = match self.squares[i] {
+                Square::Rabbit | Square::Fox => rng.gen_range(4..=8),
+                _ => 0,
+            };
+        }
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +218,29 @@ mod tests {
         assert!(nc == 2);
     }
 
+    #[test]
+    fn test_count_neighbours_wrap_corner() {
+        // with wrapping, the top-left corner sees the opposite corners
+        let mut board = Board::new(3, 3);
+        board.wrap = true;
+        board.set(2, 2, true); // diagonally opposite, wraps to be adjacent
+        board.set(0, 2, true); // directly above (wraps round)
+        board.set(2, 0, true); // directly left (wraps round)
+        let nc = count_neighbours(&board, 0, 0);
+        assert!(nc == 3);
+    }
+
+    #[test]
+    fn test_count_neighbours_no_wrap_corner() {
+        // the same cells without wrapping are not neighbours of the corner
+        let mut board = Board::new(3, 3);
+        board.set(2, 2, true);
+        board.set(0, 2, true);
+        board.set(2, 0, true);
+        let nc = count_neighbours(&board, 0, 0);
+        assert!(nc == 0);
+    }
+
     #[test]
     fn test_generation_spawn() {
         // an empty cell with three neighbours should spawn
@@ -178,30 +289,178 @@ mod tests {
     }
 }
 
-fn display_board( window : &mut RenderWindow, board : &mut Board, cell_size: u32 ) {
-    let cols = board.cols;
-    let rows = board.rows;
-    for row in 0..rows {
-        for col in 0..cols {
-            let cell_present = board.get(col, row);
-            let radius = cell_size as f32 / if cell_present { 2.0 } else { 3.0 };
-            let mut circ = CircleShape::new(radius, 30);
-            circ.set_origin((radius, radius));
-            circ.set_position(Vector2f::new(
-                (col * cell_size as usize + (cell_size / 2) as usize) as f32,
-                (row * cell_size as usize + (cell_size / 2) as usize) as f32));
-            if cell_present {
-                circ.set_fill_color(Color::rgb(0, board.get_colour(col, row), 0));
-            } else {
-                circ.set_fill_color(Color::rgb(32, 64, 32));
+// Something that can draw a board each generation. Decouples the core
+// simulation from the SFML window so other backends (e.g. a terminal) can
+// render the same `Board`.
+trait Renderer {
+    fn draw(&mut self, board: &Board);
+}
+
+// The colour a cell should be rendered with, as an (r, g, b) triple. The
+// terminal backend uses it for every cell; the windowed backend uses it for
+// live cells and predator-prey squares, and draws dead Conway cells in its
+// own dim green.
+fn cell_rgb( board : &Board, col: usize, row: usize, mode: Mode ) -> (u8, u8, u8) {
+    if mode == Mode::Predprey {
+        return match board.get_square(col, row) {
+            Square::Grass => (0, 160, 0),
+            Square::Rabbit => (224, 224, 0),
+            Square::Fox => (200, 32, 32),
+            Square::Empty => (16, 16, 16),
+        };
+    }
+    if board.get(col, row) {
+        // Map age onto a gradient: newly born cells are bright green,
+        // long-lived still lifes shift towards a dimmer, cooler blue-green.
+        let t = (board.get_age(col, row).min(60) as f32) / 60.0;
+        let g = (255.0 * (1.0 - t) + 80.0 * t) as u8;
+        let b = (160.0 * t) as u8;
+        (0, g, b)
+    } else {
+        (16, 16, 16)
+    }
+}
+
+// The original windowed backend, drawing each cell as a circle.
+struct SfmlRenderer<'a> {
+    window: &'a mut RenderWindow,
+    cell_size: u32,
+    mode: Mode,
+}
+
+impl Renderer for SfmlRenderer<'_> {
+    fn draw(&mut self, board: &Board) {
+        self.window.clear(Color::BLACK);
+        let cell_size = self.cell_size;
+        for row in 0..board.rows {
+            for col in 0..board.cols {
+                let present = if self.mode == Mode::Predprey {
+                    board.get_square(col, row) != Square::Empty
+                } else {
+                    board.get(col, row)
+                };
+                if self.mode == Mode::Predprey && !present {
+                    continue;
+                }
+                let radius = cell_size as f32 / if present { 2.0 } else { 3.0 };
+                let mut circ = CircleShape::new(radius, 30);
+                circ.set_origin((radius, radius));
+                circ.set_position(Vector2f::new(
+                    (col * cell_size as usize + (cell_size / 2) as usize) as f32,
+                    (row * cell_size as usize + (cell_size / 2) as usize) as f32));
+                if self.mode == Mode::Predprey || present {
+                    let (r, g, b) = cell_rgb(board, col, row, self.mode);
+                    circ.set_fill_color(Color::rgb(r, g, b));
+                } else {
+                    circ.set_fill_color(Color::rgb(32, 64, 32));
+                }
+                self.window.draw(&circ);
             }
-            window.draw(&circ);
         }
+        self.window.display();
     }
 }
 
+// A headless backend that draws to an ANSI terminal. Two board rows are
+// packed into each text line using the upper half-block character, with
+// 24-bit foreground/background colours, so the output stays square.
+struct TerminalRenderer {
+    mode: Mode,
+}
+
+impl Renderer for TerminalRenderer {
+    fn draw(&mut self, board: &Board) {
+        use std::io::Write;
+        // Move the cursor home so each generation overwrites the last.
+        let mut out = String::from("\x1b[H");
+        let mut row = 0;
+        while row < board.rows {
+            for col in 0..board.cols {
+                let (tr, tg, tb) = cell_rgb(board, col, row, self.mode);
+                let (br, bg, bb) = if row + 1 < board.rows {
+                    cell_rgb(board, col, row + 1, self.mode)
+                } else {
+                    (0, 0, 0)
+                };
+                out.push_str(&format!(
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}",
+                    tr, tg, tb, br, bg, bb));
+            }
+            out.push_str("\x1b[0m\n");
+            row += 2;
+        }
+        print!("{}", out);
+        std::io::stdout().flush().ok();
+    }
+}
+
+fn draw_line( board : &mut Board, x0: i32, y0: i32, x1: i32, y1: i32 ) {
+    // Bresenham's line algorithm, used to fill in the gaps left by a
+    // fast mouse drag so the drawn line is continuous.
+    let mut x0 = x0;
+    let mut y0 = y0;
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    loop {
+        if x0 >= 0 && y0 >= 0 && (x0 as usize) < board.cols && (y0 as usize) < board.rows {
+            board.set(x0 as usize, y0 as usize, true);
+        }
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+}
+
+fn neighbour_cells( col: usize, row: usize, cols: usize, rows: usize ) -> Vec<(usize, usize)> {
+    let mut out = Vec::with_capacity(8);
+    let min_col = if col > 0 { col - 1 } else { 0 };
+    let max_col = if col < cols - 1 { col + 1 } else { cols - 1 };
+    let min_row = if row > 0 { row - 1 } else { 0 };
+    let max_row = if row < rows - 1 { row + 1 } else { rows - 1 };
+    for r in min_row..=max_row {
+        for c in min_col..=max_col {
+            if c == col && r == row {
+                continue;
+            }
+            out.push((c, r));
+        }
+    }
+    out
+}
+
 fn count_neighbours( board : &Board, col: usize, row: usize ) -> i32 {
     let mut count = 0;
+    if board.wrap {
+        // Toroidal topology: the edges wrap round, so the left column
+        // neighbours the right and the top row neighbours the bottom.
+        let cols = board.cols as i32;
+        let rows = board.rows as i32;
+        for dr in -1..=1 {
+            for dc in -1..=1 {
+                if dc == 0 && dr == 0 {
+                    continue;
+                }
+                let c = ((col as i32 + cols + dc) % cols) as usize;
+                let r = ((row as i32 + rows + dr) % rows) as usize;
+                if board.get(c, r) {
+                    count += 1;
+                }
+            }
+        }
+        return count;
+    }
     let min_col = if col > 0 { col - 1 } else { 0 };
     let max_col = if col < board.cols - 1 { col + 1 } else { board.cols - 1 };
     let min_row = if row > 0 { row - 1 } else { 0 };
@@ -223,6 +482,7 @@ fn next_generation( board : &mut Board ) {
     let cols = board.cols;
     let rows = board.rows;
     let mut new_board = Board::new(cols, rows);
+    new_board.wrap = board.wrap;
     for row in 0..rows {
         for col in 0..cols {
             let colour = board.get_colour(col, row);
@@ -232,8 +492,10 @@ fn next_generation( board : &mut Board ) {
                 // occupied slot
                 if c == 2 || c == 3{
                     // an existing cell with 2-3 neighbours
-                    // will just continue to live
+                    // will just continue to live; bump its age so we
+                    // can tell stable structures from fresh ones
                     new_board.set(col, row, true);
+                    new_board.set_age(col, row, board.get_age(col, row).saturating_add(1));
                 } else {
                     new_board.set(col, row, false);
                 }
@@ -248,10 +510,153 @@ fn next_generation( board : &mut Board ) {
     *board = new_board;
 }
 
+fn step_predprey( board : &mut Board ) {
+    let cols = board.cols;
+    let rows = board.rows;
+    let mut rng = rand::thread_rng();
+
+    // Work directly on a copy of the grid, marking cells that have already
+    // been updated this tick so a moving animal isn't processed twice.
+    let mut sq = board.squares.clone();
+    let mut en = board.energy.clone();
+    let mut done = vec![false; cols * rows];
+
+    const GRASS_SPREAD: f64 = 0.10;
+    const RABBIT_GAIN: u8 = 4;
+    const FOX_GAIN: u8 = 6;
+    const REPRODUCE: u8 = 8;
+
+    for row in 0..rows {
+        for col in 0..cols {
+            let off = row * cols + col;
+            if done[off] {
+                continue;
+            }
+            match sq[off] {
+                Square::Empty => {}
+                Square::Grass => {
+                    if rng.gen_bool(GRASS_SPREAD) {
+                        let ns = neighbour_cells(col, row, cols, rows);
+                        let (nc, nr) = ns[rng.gen_range(0..ns.len())];
+                        let noff = nr * cols + nc;
+                        if sq[noff] == Square::Empty {
+                            sq[noff] = Square::Grass;
+                            done[noff] = true;
+                        }
+                    }
+                }
+                Square::Rabbit => {
+                    if en[off] == 0 {
+                        // starved
+                        sq[off] = Square::Empty;
+                        continue;
+                    }
+                    en[off] -= 1;
+                    let ns = neighbour_cells(col, row, cols, rows);
+                    let target = ns.iter()
+                        .find(|&&(c, r)| sq[r * cols + c] == Square::Grass)
+                        .or_else(|| ns.iter().find(|&&(c, r)| sq[r * cols + c] == Square::Empty))
+                        .copied();
+                    if let Some((nc, nr)) = target {
+                        let noff = nr * cols + nc;
+                        if sq[noff] == Square::Grass {
+                            en[off] = en[off].saturating_add(RABBIT_GAIN);
+                        }
+                        sq[noff] = Square::Rabbit;
+                        en[noff] = en[off];
+                        done[noff] = true;
+                        if en[off] > REPRODUCE {
+                            // reproduce: the offspring stays in the old cell,
+                            // the energy is split between parent and child
+                            let half = en[off] / 2;
+                            sq[off] = Square::Rabbit;
+                            en[off] = half;
+                            en[noff] = en[noff] - half;
+                        } else {
+                            sq[off] = Square::Empty;
+                            en[off] = 0;
+                        }
+                    }
+                }
+                Square::Fox => {
+                    if en[off] == 0 {
+                        sq[off] = Square::Empty;
+                        continue;
+                    }
+                    en[off] -= 1;
+                    let ns = neighbour_cells(col, row, cols, rows);
+                    let target = ns.iter()
+                        .find(|&&(c, r)| sq[r * cols + c] == Square::Rabbit)
+                        .or_else(|| ns.iter().find(|&&(c, r)| sq[r * cols + c] == Square::Empty))
+                        .copied();
+                    if let Some((nc, nr)) = target {
+                        let noff = nr * cols + nc;
+                        if sq[noff] == Square::Rabbit {
+                            en[off] = en[off].saturating_add(FOX_GAIN);
+                        }
+                        sq[noff] = Square::Fox;
+                        en[noff] = en[off];
+                        done[noff] = true;
+                        if en[off] > REPRODUCE {
+                            let half = en[off] / 2;
+                            sq[off] = Square::Fox;
+                            en[off] = half;
+                            en[noff] = en[noff] - half;
+                        } else {
+                            sq[off] = Square::Empty;
+                            en[off] = 0;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    board.squares = sq;
+    board.energy = en;
+}
+
+// Headless render loop used when `--terminal` is given: no SFML window is
+// opened, so the simulation can run over SSH or without a display.
+fn run_terminal( args : &Args ) {
+    let cols = 80;
+    let rows = 50;
+    let mut board = Board::new(cols, rows);
+
+    if let Some(path) = &args.load {
+        match pattern::load(Path::new(path), cols, rows) {
+            Ok(loaded) => board = loaded,
+            Err(e) => eprintln!("Could not load pattern '{}': {}", path, e),
+        }
+    } else if args.mode == Mode::Predprey {
+        board.randomise_predprey();
+    } else {
+        board.randomise(800);
+    }
+    board.wrap = args.torus;
+
+    let mut renderer = TerminalRenderer { mode: args.mode };
+    // Clear the screen and hide the cursor for the duration of the run.
+    print!("\x1b[2J\x1b[?25l");
+    loop {
+        renderer.draw(&board);
+        if args.mode == Mode::Predprey {
+            step_predprey(&mut board);
+        } else {
+            next_generation(&mut board);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
 fn main() {
 
     let args = Args::parse();
 
+    if args.terminal {
+        run_terminal(&args);
+        return;
+    }
+
     let screen_width  = VideoMode::desktop_mode().width;
     let screen_height = VideoMode::desktop_mode().height;
     let ratio: f32 = screen_width as f32 / screen_height as f32;
@@ -272,7 +677,11 @@ fn main() {
         if fs_count > 0 && args.fullscreen {Style::FULLSCREEN} else {Style::DEFAULT},
         &ContextSettings::default(),
     );
-    window.set_framerate_limit(16);
+    // Preset simulation speeds, stepped through with the +/- keys; stepping
+    // stops at the slowest and fastest presets.
+    let framerates = [2u32, 4, 8, 16, 32, 60];
+    let mut framerate_idx = 3;
+    window.set_framerate_limit(framerates[framerate_idx]);
     window.set_position(Vector2i::new(50, 50));
     window.set_mouse_cursor_visible(false);
 
@@ -280,13 +689,52 @@ fn main() {
     let cols = (window_width / cell_size) as usize;
     let mut board = Board::new(cols, rows);
 
-    board.randomise(2000);
+    if let Some(path) = &args.load {
+        match pattern::load(Path::new(path), cols, rows) {
+            Ok(loaded) => board = loaded,
+            Err(e) => eprintln!("Could not load pattern '{}': {}", path, e),
+        }
+    } else if args.mode == Mode::Predprey {
+        board.randomise_predprey();
+    } else {
+        board.randomise(2000);
+    }
+    board.wrap = args.torus;
+
+    // Tracks the last grid cell the mouse was over while the left button
+    // is held, so drags can be joined up into a continuous line.
+    let mut drawing: Option<(i32, i32)> = None;
+
+    // When paused the simulation stops advancing so a configuration can be
+    // studied or hand-edited; Space advances a single generation.
+    let mut paused = false;
 
     // Main Loop
     while window.is_open() {
         while let Some(event) = window.poll_event() {
             match event {
                 Event::Closed => window.close(),
+                Event::MouseButtonPressed { button: mouse::Button::Left, x, y } => {
+                    let col = x / cell_size as i32;
+                    let row = y / cell_size as i32;
+                    if col >= 0 && row >= 0 && (col as usize) < board.cols
+                        && (row as usize) < board.rows {
+                        let current = board.get(col as usize, row as usize);
+                        board.set(col as usize, row as usize, !current);
+                    }
+                    drawing = Some((col, row));
+                },
+                Event::MouseButtonReleased { button: mouse::Button::Left, .. } => {
+                    drawing = None;
+                },
+                Event::MouseMoved { x, y } => {
+                    if let Some((px, py)) = drawing {
+                        let col = x / cell_size as i32;
+                        let row = y / cell_size as i32;
+                        draw_line(&mut board, px, py, col, row);
+                        drawing = Some((col, row));
+                    }
+                },
                 Event::KeyReleased { code, .. } => {
                     match code {
                         Key::Escape => {
@@ -297,7 +745,41 @@ fn main() {
                         },
                         Key::R => {
                             board.clear();
-                            board.randomise(2000);
+                            if args.mode == Mode::Predprey {
+                                board.randomise_predprey();
+                            } else {
+                                board.randomise(2000);
+                            }
+                        },
+                        Key::P => {
+                            paused = !paused;
+                        },
+                        Key::S => {
+                            let rle = pattern::to_rle(&board);
+                            if let Err(e) = std::fs::write("pattern.rle", rle) {
+                                eprintln!("Could not save pattern: {}", e);
+                            }
+                        },
+                        Key::Space => {
+                            if paused {
+                                if args.mode == Mode::Predprey {
+                                    step_predprey(&mut board);
+                                } else {
+                                    next_generation(&mut board);
+                                }
+                            }
+                        },
+                        Key::Add | Key::Equal => {
+                            if framerate_idx < framerates.len() - 1 {
+                                framerate_idx += 1;
+                                window.set_framerate_limit(framerates[framerate_idx]);
+                            }
+                        },
+                        Key::Subtract | Key::Hyphen => {
+                            if framerate_idx > 0 {
+                                framerate_idx -= 1;
+                                window.set_framerate_limit(framerates[framerate_idx]);
+                            }
                         },
                         _ => {}
                     }
@@ -305,9 +787,20 @@ fn main() {
                 _ => {} // ignore other events
             }
         }
-        window.clear(Color::BLACK);
-        display_board(&mut window, &mut board, cell_size);
-        next_generation(&mut board);
-        window.display();
+        {
+            let mut renderer = SfmlRenderer {
+                window: &mut window,
+                cell_size,
+                mode: args.mode,
+            };
+            renderer.draw(&board);
+        }
+        if !paused {
+            if args.mode == Mode::Predprey {
+                step_predprey(&mut board);
+            } else {
+                next_generation(&mut board);
+            }
+        }
     }
 }
@@ -0,0 +1,218 @@
+// Loading and saving of Life patterns in the two common on-disk formats:
+// the run-length encoded `.rle` format and the simpler plaintext `.cells`
+// format. Loaded patterns are centred on the current board dimensions.
+
+use crate::Board;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// A pattern as a set of live cell coordinates relative to its own top-left
+// corner, together with its bounding dimensions.
+struct Pattern {
+    width: usize,
+    height: usize,
+    live: Vec<(usize, usize)>,
+}
+
+// Load a pattern from disk, choosing the parser by file extension, and place
+// it centred on a fresh board of the given dimensions.
+pub fn load(path: &Path, cols: usize, rows: usize) -> io::Result<Board> {
+    let contents = fs::read_to_string(path)?;
+    let pattern = match path.extension().and_then(|e| e.to_str()) {
+        Some("rle") => parse_rle(&contents),
+        _ => parse_plaintext(&contents),
+    };
+    Ok(place_centered(cols, rows, &pattern))
+}
+
+fn parse_rle(text: &str) -> Pattern {
+    let mut width = 0;
+    let mut height = 0;
+    let mut live = Vec::new();
+    let mut x = 0;
+    let mut y = 0;
+    let mut count = String::new();
+    let mut in_header = true;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        if in_header && line.starts_with('x') {
+            for part in line.split(',') {
+                let mut kv = part.split('=');
+                let key = kv.next().unwrap_or("").trim();
+                let val = kv.next().unwrap_or("").trim();
+                match key {
+                    "x" => width = val.parse().unwrap_or(0),
+                    "y" => height = val.parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+            in_header = false;
+            continue;
+        }
+        for ch in line.chars() {
+            match ch {
+                '0'..='9' => count.push(ch),
+                'b' | 'o' | '$' => {
+                    let n = if count.is_empty() { 1 } else { count.parse().unwrap_or(1) };
+                    count.clear();
+                    match ch {
+                        'b' => x += n,
+                        'o' => {
+                            for _ in 0..n {
+                                live.push((x, y));
+                                x += 1;
+                            }
+                        }
+                        '$' => {
+                            y += n;
+                            x = 0;
+                        }
+                        _ => {}
+                    }
+                }
+                '!' => return Pattern { width, height, live },
+                _ => {}
+            }
+        }
+    }
+    Pattern { width, height, live }
+}
+
+fn parse_plaintext(text: &str) -> Pattern {
+    let mut live = Vec::new();
+    let mut width = 0;
+    let mut y = 0;
+    for line in text.lines() {
+        if line.starts_with('!') {
+            // comment line in the .cells format
+            continue;
+        }
+        for (x, ch) in line.chars().enumerate() {
+            if ch == 'O' || ch == '*' {
+                live.push((x, y));
+            }
+        }
+        if line.len() > width {
+            width = line.len();
+        }
+        y += 1;
+    }
+    Pattern { width, height: y, live }
+}
+
+fn place_centered(cols: usize, rows: usize, pattern: &Pattern) -> Board {
+    let mut board = Board::new(cols, rows);
+    let off_x = cols.saturating_sub(pattern.width) / 2;
+    let off_y = rows.saturating_sub(pattern.height) / 2;
+    for &(x, y) in &pattern.live {
+        let c = off_x + x;
+        let r = off_y + y;
+        if c < cols && r < rows {
+            board.set(c, r, true);
+        }
+    }
+    board
+}
+
+// Serialise the live cells of a board to a run-length encoded string,
+// cropped to their bounding box.
+pub fn to_rle(board: &Board) -> String {
+    let mut min_c = board.cols;
+    let mut min_r = board.rows;
+    let mut max_c = 0;
+    let mut max_r = 0;
+    let mut any = false;
+    for r in 0..board.rows {
+        for c in 0..board.cols {
+            if board.get(c, r) {
+                any = true;
+                min_c = min_c.min(c);
+                max_c = max_c.max(c);
+                min_r = min_r.min(r);
+                max_r = max_r.max(r);
+            }
+        }
+    }
+    if !any {
+        return "x = 0, y = 0\n!\n".to_string();
+    }
+    let mut out = format!("x = {}, y = {}\n", max_c - min_c + 1, max_r - min_r + 1);
+    let mut body = String::new();
+    for r in min_r..=max_r {
+        let mut runs: Vec<(char, usize)> = Vec::new();
+        for c in min_c..=max_c {
+            let tag = if board.get(c, r) { 'o' } else { 'b' };
+            match runs.last_mut() {
+                Some(last) if last.0 == tag => last.1 += 1,
+                _ => runs.push((tag, 1)),
+            }
+        }
+        // trailing dead cells in a row are implicit and can be dropped
+        if let Some(last) = runs.last() {
+            if last.0 == 'b' {
+                runs.pop();
+            }
+        }
+        for (tag, n) in runs {
+            if n == 1 {
+                body.push(tag);
+            } else {
+                body.push_str(&format!("{}{}", n, tag));
+            }
+        }
+        if r < max_r {
+            body.push('$');
+        }
+    }
+    body.push('!');
+    out.push_str(&body);
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rle_block() {
+        // a 2x2 block
+        let pattern = parse_rle("x = 2, y = 2\n2o$2o!\n");
+        assert!(pattern.width == 2);
+        assert!(pattern.height == 2);
+        let mut live = pattern.live.clone();
+        live.sort();
+        let mut expected = vec![(0, 0), (1, 0), (0, 1), (1, 1)];
+        expected.sort();
+        assert!(live == expected);
+    }
+
+    #[test]
+    fn test_parse_plaintext_glider() {
+        let pattern = parse_plaintext("!Name: glider\n.O.\n..O\nOOO\n");
+        assert!(pattern.height == 3);
+        assert!(pattern.live.contains(&(1, 0)));
+        assert!(pattern.live.contains(&(2, 1)));
+        assert!(pattern.live.contains(&(0, 2)));
+        assert!(pattern.live.contains(&(1, 2)));
+        assert!(pattern.live.contains(&(2, 2)));
+    }
+
+    #[test]
+    fn test_rle_round_trip() {
+        // a glider placed on a board should survive a save/load cycle
+        let glider = "x = 3, y = 3\nbo$2bo$3o!\n";
+        let board = place_centered(10, 10, &parse_rle(glider));
+        let serialised = to_rle(&board);
+        let reparsed = parse_rle(&serialised);
+        let mut a: Vec<_> = parse_rle(glider).live;
+        let mut b = reparsed.live;
+        a.sort();
+        b.sort();
+        assert!(a == b);
+    }
+}